@@ -0,0 +1,351 @@
+//! Vendor-agnostic speech-to-text backend.
+//!
+//! `main` no longer talks to `azure_cognitiveservices_speech` directly.
+//! Instead it drives a [`TranscriptionBackend`], which normalizes
+//! whatever shape a vendor hands back (Azure's detailed JSON, Google's
+//! streaming protobuf, ...) into a stream of [`WordToken`]s. This is
+//! where the Offset/Duration 100-ns conversion math and similar
+//! vendor-specific bookkeeping live, so the diarization/grouping and
+//! output code downstream never has to know which vendor produced them.
+
+use async_trait::async_trait;
+use azure_cognitiveservices_speech::audio::AudioConfig;
+use azure_cognitiveservices_speech::speech::{
+    CancellationReason, ResultReason, SpeechConfig, SpeechRecognizer, SpeechSynthesisOutputFormat,
+};
+use std::collections::HashMap;
+use std::env;
+use std::io::Write;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex, Notify};
+
+use crate::streaming;
+
+/// Error type shared across backends, since each vendor SDK brings its
+/// own error type and we don't want that leaking into `main`.
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// A single recognized word, normalized across vendors.
+#[derive(Debug, Clone)]
+pub struct WordToken {
+    pub speaker_id: String,
+    pub text: String,
+    pub start_s: f64,
+    pub end_s: f64,
+    pub confidence: f64,
+    /// Whether this word closes out a sentence, per the vendor's
+    /// punctuated display text (Azure's NBest `Display`, Google's
+    /// alternative `transcript`). `text` itself never carries
+    /// punctuation, so callers that need sentence boundaries (e.g. the
+    /// SRT/VTT cue builder) must use this rather than inspecting `text`.
+    pub ends_sentence: bool,
+}
+
+/// Where a backend should read its audio from.
+pub enum AudioSource {
+    /// A WAV file on disk.
+    WavFile(String),
+    /// Raw PCM chunks arriving over a channel, fed by [`streaming::pump_audio`]
+    /// or [`crate::decode::decode_to_pcm`].
+    PcmChunks(mpsc::UnboundedReceiver<Vec<u8>>),
+}
+
+/// A speech-to-text vendor.
+#[async_trait]
+pub trait TranscriptionBackend: Send + Sync {
+    /// Runs a full recognition session over `audio`, sending each
+    /// recognized word to `tokens` as it arrives and returning once the
+    /// session has ended.
+    async fn transcribe(&self, audio: AudioSource, tokens: mpsc::UnboundedSender<WordToken>) -> Result<()>;
+}
+
+/// Resolves which backend to use from the `TRANSCRIBE_BACKEND` env var
+/// (`azure` or `google`), defaulting to Azure.
+pub fn resolve_backend() -> Box<dyn TranscriptionBackend> {
+    match env::var("TRANSCRIBE_BACKEND").as_deref() {
+        Ok("google") => Box::new(GoogleBackend::new()),
+        _ => Box::new(AzureBackend::new()),
+    }
+}
+
+/// Wraps `azure_cognitiveservices_speech`. Converts its 100-ns
+/// `Offset`/`Duration` ticks into the `f64` seconds used by `WordToken`.
+pub struct AzureBackend;
+
+impl AzureBackend {
+    pub fn new() -> Self {
+        AzureBackend
+    }
+}
+
+#[async_trait]
+impl TranscriptionBackend for AzureBackend {
+    async fn transcribe(&self, audio: AudioSource, tokens: mpsc::UnboundedSender<WordToken>) -> Result<()> {
+        let speech_key = env::var("AZURE_SPEECH_KEY")?;
+        let service_region = env::var("AZURE_SERVICE_REGION")?;
+
+        let speech_config = SpeechConfig::from_subscription(&speech_key, &service_region)?;
+        speech_config.set_speech_recognition_language("en-US")?;
+        speech_config.request_word_level_timestamps()?;
+        speech_config.enable_dictation()?;
+        speech_config.set_output_format(SpeechSynthesisOutputFormat::DetailedJson)?;
+
+        let mut chunk_forwarder = None;
+        let audio_config = match audio {
+            AudioSource::WavFile(path) => AudioConfig::from_wav_file_input(&path)?,
+            AudioSource::PcmChunks(mut chunks) => {
+                let push_stream = streaming::new_push_stream()?;
+                let forward_stream = push_stream.clone();
+                chunk_forwarder = Some(tokio::spawn(async move {
+                    while let Some(chunk) = chunks.recv().await {
+                        forward_stream.write(&chunk)?;
+                    }
+                    forward_stream.close()?;
+                    azure_cognitiveservices_speech::Result::Ok(())
+                }));
+                AudioConfig::from_stream_input(push_stream)?
+            }
+        };
+
+        let recognizer = SpeechRecognizer::new(speech_config, Some(audio_config))?;
+        let speakers = Arc::new(Mutex::new(HashMap::new()));
+
+        // `session_stopped`/`canceled` are the real end-of-stream signals;
+        // `Notify` lets us await one of them instead of busy-spinning on
+        // `session_started` and stopping almost immediately.
+        let session_ended = Arc::new(Notify::new());
+        let cancellation: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+        recognizer
+            .recognizing
+            .connect(move |event| {
+                if let ResultReason::RecognizingSpeech = event.result.reason {
+                    // Partial hypothesis: overwrite the current line so
+                    // long files show live progress instead of silence.
+                    eprint!("\r{:<80}", event.result.text);
+                    let _ = std::io::stderr().flush();
+                }
+            })
+            .await;
+
+        recognizer
+            .recognized
+            .connect({
+                let speakers = Arc::clone(&speakers);
+                let tokens = tokens.clone();
+                move |event| {
+                    if let ResultReason::RecognizedSpeech = event.result.reason {
+                        let result_json = event.result.priv_text.as_ref().unwrap();
+                        let result_json: serde_json::Value = serde_json::from_str(result_json).unwrap();
+
+                        if let Some(nbest) = result_json.get("NBest").and_then(|n| n.as_array()) {
+                            for nbest_item in nbest {
+                                // `Display` carries the punctuated sentence
+                                // text; `Words` carries the unpunctuated
+                                // per-word breakdown. Only the last word in
+                                // the list actually closes the sentence.
+                                let display = nbest_item.get("Display").and_then(|d| d.as_str()).unwrap_or("");
+                                let sentence_ends = matches!(display.chars().last(), Some('.' | '!' | '?'));
+                                let Some(words) = nbest_item.get("Words").and_then(|w| w.as_array()) else {
+                                    continue;
+                                };
+                                let last_index = words.len().saturating_sub(1);
+
+                                for (index, word) in words.iter().enumerate() {
+                                    let speaker_id =
+                                        word.get("SpeakerId").and_then(|s| s.as_str()).unwrap_or("Unknown");
+                                    let mut locked_speakers = speakers.lock().await;
+                                    let speaker = locked_speakers
+                                        .entry(speaker_id.to_string())
+                                        .or_insert_with(|| format!("Speaker {}", locked_speakers.len() + 1))
+                                        .clone();
+                                    let text = word.get("Word").and_then(|w| w.as_str()).unwrap().to_string();
+                                    let offset = word.get("Offset").and_then(|o| o.as_f64()).unwrap();
+                                    let duration = word.get("Duration").and_then(|d| d.as_f64()).unwrap();
+                                    let confidence = word.get("Confidence").and_then(|c| c.as_f64()).unwrap_or(1.0);
+
+                                    let _ = tokens.send(WordToken {
+                                        speaker_id: speaker,
+                                        text,
+                                        start_s: offset / 10_000_000.0,
+                                        end_s: (offset + duration) / 10_000_000.0,
+                                        confidence,
+                                        ends_sentence: sentence_ends && index == last_index,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            })
+            .await;
+
+        recognizer
+            .session_stopped
+            .connect({
+                let session_ended = Arc::clone(&session_ended);
+                move |_event| {
+                    session_ended.notify_one();
+                }
+            })
+            .await;
+
+        recognizer
+            .canceled
+            .connect({
+                let session_ended = Arc::clone(&session_ended);
+                let cancellation = Arc::clone(&cancellation);
+                move |event| {
+                    if event.reason != CancellationReason::EndOfStream {
+                        let mut locked = cancellation.lock().await;
+                        *locked = Some(format!("{:?}: {}", event.reason, event.error_details));
+                    }
+                    session_ended.notify_one();
+                }
+            })
+            .await;
+
+        println!("Starting transcription and diarization...");
+        recognizer.start_continuous_recognition().await?;
+        session_ended.notified().await;
+        recognizer.stop_continuous_recognition().await?;
+        eprintln!();
+
+        if let Some(forwarder) = chunk_forwarder {
+            forwarder.await??;
+        }
+
+        if let Some(reason) = cancellation.lock().await.take() {
+            return Err(format!("recognition canceled: {}", reason).into());
+        }
+        Ok(())
+    }
+}
+
+/// Wraps Google Cloud Speech-to-Text's streaming API
+/// (`SpeechClient::streaming_recognize`).
+pub struct GoogleBackend;
+
+impl GoogleBackend {
+    pub fn new() -> Self {
+        GoogleBackend
+    }
+}
+
+#[async_trait]
+impl TranscriptionBackend for GoogleBackend {
+    async fn transcribe(&self, audio: AudioSource, tokens: mpsc::UnboundedSender<WordToken>) -> Result<()> {
+        use google_api_proto::google::cloud::speech::v1::{
+            recognition_config::AudioEncoding, speech_client::SpeechClient,
+            streaming_recognize_request::StreamingRequest, RecognitionConfig, StreamingRecognitionConfig,
+            StreamingRecognizeRequest,
+        };
+
+        // Google's streaming API expects headerless PCM; a `.wav` file
+        // carries a RIFF/fmt/data header in front of the payload, whose
+        // `fmt ` chunk also tells us the real sample rate rather than
+        // assuming 16 kHz.
+        let (sample_rate_hertz, wav_pcm) = match &audio {
+            AudioSource::WavFile(path) => {
+                let (sample_rate, pcm) = crate::wav::read_pcm(path).await?;
+                (sample_rate, Some(pcm))
+            }
+            AudioSource::PcmChunks(_) => (16_000, None),
+        };
+
+        let config = RecognitionConfig {
+            encoding: AudioEncoding::Linear16 as i32,
+            sample_rate_hertz,
+            audio_channel_count: 1,
+            language_code: "en-US".to_string(),
+            enable_automatic_punctuation: true,
+            enable_speaker_diarization: true,
+            ..Default::default()
+        };
+        let streaming_config = StreamingRecognitionConfig {
+            config: Some(config),
+            interim_results: false,
+            ..Default::default()
+        };
+
+        let mut client = SpeechClient::connect("https://speech.googleapis.com").await?;
+
+        let (request_tx, request_rx) = mpsc::channel(16);
+        request_tx
+            .send(StreamingRecognizeRequest {
+                streaming_request: Some(StreamingRequest::StreamingConfig(streaming_config)),
+            })
+            .await?;
+
+        match audio {
+            AudioSource::WavFile(_) => {
+                let pcm = wav_pcm.expect("wav PCM was parsed above for the WavFile branch");
+                request_tx
+                    .send(StreamingRecognizeRequest {
+                        streaming_request: Some(StreamingRequest::AudioContent(pcm)),
+                    })
+                    .await?;
+                // Close the request stream so the server ends the session.
+                drop(request_tx);
+            }
+            AudioSource::PcmChunks(mut chunks) => {
+                tokio::spawn(async move {
+                    while let Some(chunk) = chunks.recv().await {
+                        let request = StreamingRecognizeRequest {
+                            streaming_request: Some(StreamingRequest::AudioContent(chunk)),
+                        };
+                        if request_tx.send(request).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        }
+
+        let request_stream = tokio_stream::wrappers::ReceiverStream::new(request_rx);
+        let mut responses = client
+            .streaming_recognize(tonic::Request::new(request_stream))
+            .await?
+            .into_inner();
+
+        let mut speakers = HashMap::new();
+        while let Some(response) = responses.message().await? {
+            for result in response.results {
+                for alternative in result.alternatives {
+                    // `transcript` carries the punctuated sentence text;
+                    // `words` carries the unpunctuated per-word
+                    // breakdown. Only the last word actually closes it.
+                    let sentence_ends =
+                        matches!(alternative.transcript.chars().last(), Some('.' | '!' | '?'));
+                    let last_index = alternative.words.len().saturating_sub(1);
+
+                    for (index, word_info) in alternative.words.into_iter().enumerate() {
+                        let speaker_tag = word_info.speaker_tag.max(1);
+                        let speaker_count = speakers.len();
+                        let speaker = speakers
+                            .entry(speaker_tag)
+                            .or_insert_with(|| format!("Speaker {}", speaker_count + 1))
+                            .clone();
+                        let start_s = word_info.start_time.map(duration_to_secs).unwrap_or(0.0);
+                        let end_s = word_info.end_time.map(duration_to_secs).unwrap_or(start_s);
+
+                        let _ = tokens.send(WordToken {
+                            speaker_id: speaker,
+                            text: word_info.word,
+                            start_s,
+                            end_s,
+                            confidence: alternative.confidence as f64,
+                            ends_sentence: sentence_ends && index == last_index,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn duration_to_secs(d: prost_types::Duration) -> f64 {
+    d.seconds as f64 + d.nanos as f64 / 1_000_000_000.0
+}
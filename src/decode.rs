@@ -0,0 +1,117 @@
+//! Decodes arbitrary audio/video containers (MP3, FLAC, an MP4/MKV audio
+//! track, ...) into the PCM stream the recognizer's push input expects,
+//! using a GStreamer `uridecodebin ! audioconvert ! audioresample !
+//! capsfilter ! appsink` pipeline. This lets `SOUND_FILE` point at
+//! anything GStreamer can demux/decode instead of requiring a
+//! pre-converted WAV.
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use std::str::FromStr;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+
+/// The format the recognizer's push input expects, matching
+/// `AudioStreamFormat::get_default_input_format()` in `streaming.rs`.
+const PCM_CAPS: &str = "audio/x-raw,format=S16LE,rate=16000,channels=1";
+
+/// Runs a decode pipeline for `uri`, pushing each decoded PCM buffer onto
+/// `chunk_tx` as it arrives off the `appsink`. Returns once the pipeline
+/// reaches end-of-stream or reports an error on its bus.
+pub async fn decode_to_pcm(uri: &str, chunk_tx: mpsc::UnboundedSender<Vec<u8>>) -> crate::backend::Result<()> {
+    gst::init()?;
+
+    // Built element-by-element (rather than `gst::parse::launch`) so
+    // `uri` is set as a property, not spliced into a pipeline
+    // description string where spaces or launch-syntax characters in
+    // the path would break the parser.
+    let pipeline = gst::Pipeline::new();
+    let uridecodebin = gst::ElementFactory::make("uridecodebin").property("uri", uri).build()?;
+    let audioconvert = gst::ElementFactory::make("audioconvert").build()?;
+    let audioresample = gst::ElementFactory::make("audioresample").build()?;
+    let capsfilter = gst::ElementFactory::make("capsfilter")
+        .property("caps", gst::Caps::from_str(PCM_CAPS)?)
+        .build()?;
+    let appsink = gst_app::AppSink::builder().name("sink").build();
+
+    pipeline.add_many(&[
+        &uridecodebin,
+        &audioconvert,
+        &audioresample,
+        &capsfilter,
+        appsink.upcast_ref(),
+    ])?;
+    gst::Element::link_many(&[&audioconvert, &audioresample, &capsfilter, appsink.upcast_ref()])?;
+
+    // `uridecodebin` exposes its source pad only once it has probed the
+    // uri, so it links to the rest of the pipeline dynamically.
+    let audioconvert_sink = audioconvert.static_pad("sink").expect("audioconvert has a sink pad");
+    uridecodebin.connect_pad_added(move |_element, pad| {
+        if pad.current_caps().map(|caps| caps.to_string().starts_with("audio/")).unwrap_or(true)
+            && !audioconvert_sink.is_linked()
+        {
+            let _ = pad.link(&audioconvert_sink);
+        }
+    });
+
+    appsink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample(move |sink| {
+                let sample = sink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
+                let buffer = sample.buffer().ok_or(gst::FlowError::Error)?;
+                let map = buffer.map_readable().map_err(|_| gst::FlowError::Error)?;
+                let _ = chunk_tx.send(map.as_slice().to_vec());
+                Ok(gst::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+
+    pipeline.set_state(gst::State::Playing)?;
+
+    let bus = pipeline.bus().expect("pipeline has a bus");
+    let mut messages = bus.stream();
+    while let Some(message) = messages.next().await {
+        match message.view() {
+            gst::MessageView::Eos(_) => break,
+            gst::MessageView::Error(err) => {
+                pipeline.set_state(gst::State::Null)?;
+                return Err(format!(
+                    "gstreamer decode error from {:?}: {}",
+                    err.src().map(|source| source.path_string()),
+                    err.error()
+                )
+                .into());
+            }
+            _ => {}
+        }
+    }
+
+    pipeline.set_state(gst::State::Null)?;
+    Ok(())
+}
+
+/// Builds a proper `file://` URI for `path`, resolving a relative path
+/// against the current directory first. `glib::filename_to_uri`
+/// percent-encodes characters (spaces, `#`, `%`, `?`, ...) that would
+/// otherwise be misread as part of a URI's query/fragment syntax, unlike
+/// naive `format!("file://{}", path)` string concatenation.
+pub fn file_uri(path: &str) -> crate::backend::Result<String> {
+    let path = std::env::current_dir()?.join(path);
+    Ok(gst::glib::filename_to_uri(&path, None)?.to_string())
+}
+
+/// Files with one of these extensions are handed to the backend as
+/// `AudioSource::WavFile`; everything else goes through the GStreamer
+/// decode front-end.
+const NATIVE_WAV_EXTENSIONS: &[&str] = &["wav"];
+
+/// Whether `path` needs GStreamer decoding rather than the native WAV
+/// file input.
+pub fn needs_decoding(path: &str) -> bool {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase());
+    !matches!(extension, Some(extension) if NATIVE_WAV_EXTENSIONS.contains(&extension.as_str()))
+}
@@ -0,0 +1,111 @@
+//! Groups word-level `WordToken`s into speaker turns/utterances, so the
+//! markdown transcript reads as sentences rather than one line per word.
+
+use crate::backend::WordToken;
+use std::env;
+
+/// A speaker turn: one or more consecutive same-speaker words merged into
+/// a single utterance with a combined text span and aggregate timing.
+#[derive(Debug, Clone)]
+pub struct Utterance {
+    pub speaker: String,
+    pub text: String,
+    pub start_s: f64,
+    pub end_s: f64,
+}
+
+/// Default inter-word gap, in seconds, past which we start a new
+/// utterance even if the speaker hasn't changed.
+const DEFAULT_SILENCE_THRESHOLD_S: f64 = 0.7;
+
+fn silence_threshold_s() -> f64 {
+    env::var("SILENCE_THRESHOLD_S")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_SILENCE_THRESHOLD_S)
+}
+
+/// Coalesces adjacent words sharing the same speaker into utterances,
+/// breaking the turn when the speaker changes or when the gap between a
+/// word and the one before it exceeds the silence threshold.
+pub fn group_into_utterances(words: &[WordToken]) -> Vec<Utterance> {
+    let threshold = silence_threshold_s();
+    let mut utterances: Vec<Utterance> = Vec::new();
+
+    for word in words {
+        let start_new_turn = match utterances.last() {
+            None => true,
+            Some(utterance) => {
+                utterance.speaker != word.speaker_id || word.start_s - utterance.end_s > threshold
+            }
+        };
+
+        if start_new_turn {
+            utterances.push(Utterance {
+                speaker: word.speaker_id.clone(),
+                text: word.text.clone(),
+                start_s: word.start_s,
+                end_s: word.end_s,
+            });
+        } else {
+            let utterance = utterances.last_mut().unwrap();
+            utterance.text.push(' ');
+            utterance.text.push_str(&word.text);
+            utterance.end_s = word.end_s;
+        }
+    }
+
+    utterances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(speaker_id: &str, text: &str, start_s: f64, end_s: f64) -> WordToken {
+        WordToken {
+            speaker_id: speaker_id.to_string(),
+            text: text.to_string(),
+            start_s,
+            end_s,
+            confidence: 1.0,
+            ends_sentence: false,
+        }
+    }
+
+    #[test]
+    fn breaks_on_speaker_change() {
+        let words = vec![word("Speaker 1", "hello", 0.0, 0.5), word("Speaker 2", "hi", 0.5, 1.0)];
+
+        let utterances = group_into_utterances(&words);
+
+        assert_eq!(utterances.len(), 2);
+        assert_eq!(utterances[0].text, "hello");
+        assert_eq!(utterances[1].text, "hi");
+    }
+
+    #[test]
+    fn coalesces_same_speaker_within_threshold() {
+        let words = vec![word("Speaker 1", "hello", 0.0, 0.5), word("Speaker 1", "there", 0.6, 1.0)];
+
+        let utterances = group_into_utterances(&words);
+
+        assert_eq!(utterances.len(), 1);
+        assert_eq!(utterances[0].text, "hello there");
+    }
+
+    #[test]
+    fn breaks_on_silence_gap_past_threshold() {
+        let threshold = silence_threshold_s();
+        let words = vec![
+            word("Speaker 1", "hello", 0.0, 0.5),
+            word("Speaker 1", "there", 0.5 + threshold + 1.0, 0.5 + threshold + 1.5),
+        ];
+
+        let utterances = group_into_utterances(&words);
+
+        assert_eq!(utterances.len(), 2);
+        assert_eq!(utterances[0].text, "hello");
+        assert_eq!(utterances[1].text, "there");
+    }
+}
@@ -1,101 +1,144 @@
-use azure_cognitiveservices_speech::audio::AudioConfig;
-use azure_cognitiveservices_speech::speech::{
-    ResultReason, SpeechConfig, SpeechRecognizer, SpeechSynthesisOutputFormat,
-};
+mod backend;
+mod decode;
+mod diarize;
+mod streaming;
+mod subtitles;
+mod translate;
+mod wav;
+
+use backend::{AudioSource, WordToken};
 use dotenv::dotenv;
-use std::collections::HashMap;
 use std::env;
 use std::fs::File;
-use std::io::Write;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use subtitles::{OutputFormat, WordEntry};
+use tokio::sync::mpsc;
+
+/// Where the recognizer should pull its audio from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputMode {
+    /// Read a fixed audio file from disk (`SOUND_FILE`); non-WAV files are
+    /// decoded through GStreamer.
+    File,
+    /// Pump audio chunks from stdin as they arrive.
+    Stream,
+}
+
+impl InputMode {
+    /// Resolves the mode from `--mode <file|stream>` on the command line,
+    /// falling back to the `TRANSCRIBE_MODE` env var, defaulting to `file`.
+    fn resolve() -> Self {
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--mode" {
+                if let Some(value) = args.next() {
+                    return Self::parse(&value);
+                }
+            }
+        }
+        match env::var("TRANSCRIBE_MODE") {
+            Ok(value) => Self::parse(&value),
+            Err(_) => InputMode::File,
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "stream" => InputMode::Stream,
+            _ => InputMode::File,
+        }
+    }
+}
 
 #[tokio::main]
-async fn main() -> azure_cognitiveservices_speech::Result<()> {
+async fn main() -> backend::Result<()> {
     dotenv().ok();
 
-    let speech_key = env::var("AZURE_SPEECH_KEY").expect("AZURE_SPEECH_KEY must be set");
-    let service_region = env::var("AZURE_SERVICE_REGION").expect("AZURE_SERVICE_REGION must be set");
-    let audio_filename = env::var("SOUND_FILE").expect("SOUND_FILE must be set");
+    let mode = InputMode::resolve();
     let output_markdown_filename = env::var("OUTPUT_FILE").expect("OUTPUT_FILE must be set");
 
-    let speech_config = SpeechConfig::from_subscription(&speech_key, &service_region)?;
-    speech_config.set_speech_recognition_language("en-US")?;
-    speech_config.request_word_level_timestamps()?;
-    speech_config.enable_dictation()?;
-    speech_config.set_output_format(SpeechSynthesisOutputFormat::DetailedJson)?;
-
-    let audio_config = AudioConfig::from_wav_file_input(&audio_filename)?;
-    let recognizer = SpeechRecognizer::new(speech_config, Some(audio_config))?;
-
-    let transcript_data = Arc::new(Mutex::new(Vec::new()));
-    let speakers = Arc::new(Mutex::new(HashMap::new()));
-
-    recognizer.recognized.connect({
-        let transcript_data = Arc::clone(&transcript_data);
-        let speakers = Arc::clone(&speakers);
-        move |event| {
-            if let ResultReason::RecognizedSpeech = event.result.reason {
-                let result_json = event.result.priv_text.as_ref().unwrap();
-                let result_json: serde_json::Value = serde_json::from_str(result_json).unwrap();
-
-                let mut locked_transcript = transcript_data.lock().await;
-                locked_transcript.push(result_json.clone());
-
-                if let Some(nbest) = result_json.get("NBest").and_then(|n| n.as_array()) {
-                    for sentence in nbest.iter().flat_map(|s| s.get("Words").and_then(|w| w.as_array())) {
-                        for word in sentence {
-                            let speaker_id = word.get("SpeakerId").and_then(|s| s.as_str()).unwrap_or("Unknown");
-                            let mut locked_speakers = speakers.lock().await;
-                            let speaker = locked_speakers
-                                .entry(speaker_id.to_string())
-                                .or_insert_with(|| format!("Speaker {}", locked_speakers.len() + 1))
-                                .clone();
-                            let text = word.get("Word").and_then(|w| w.as_str()).unwrap();
-                            let start_time = word.get("Offset").and_then(|o| o.as_f64()).unwrap() / 10_000_000.0;
-                            let end_time = (word.get("Offset").and_then(|o| o.as_f64()).unwrap()
-                                + word.get("Duration").and_then(|d| d.as_f64()).unwrap())
-                                / 10_000_000.0;
-
-                            println!("- **{}** ({:.2}s - {:.2}s): {}", speaker, start_time, end_time, text);
-                        }
-                    }
-                }
+    let (audio_source, audio_pump) = match mode {
+        InputMode::File => {
+            let audio_filename = env::var("SOUND_FILE").expect("SOUND_FILE must be set");
+            if decode::needs_decoding(&audio_filename) {
+                let uri = decode::file_uri(&audio_filename)?;
+                let (chunk_tx, chunk_rx) = mpsc::unbounded_channel();
+                let pump = tokio::spawn(async move { decode::decode_to_pcm(&uri, chunk_tx).await });
+                (AudioSource::PcmChunks(chunk_rx), Some(pump))
+            } else {
+                (AudioSource::WavFile(audio_filename), None)
             }
         }
-    }).await;
+        InputMode::Stream => {
+            let (chunk_tx, chunk_rx) = mpsc::unbounded_channel();
+            let pump = tokio::spawn(streaming::pump_audio(tokio::io::stdin(), chunk_tx));
+            (AudioSource::PcmChunks(chunk_rx), Some(pump))
+        }
+    };
+
+    let backend = backend::resolve_backend();
+
+    let (tokens_tx, mut tokens_rx) = mpsc::unbounded_channel::<WordToken>();
+    let transcribe_task = tokio::spawn(async move { backend.transcribe(audio_source, tokens_tx).await });
 
     println!("Starting transcription and diarization...");
-    recognizer.start_continuous_recognition().await?;
 
-    while !recognizer.session_started().await {}
-    recognizer.stop_continuous_recognition().await?;
+    let mut tokens = Vec::new();
+    while let Some(token) = tokens_rx.recv().await {
+        println!(
+            "[live] {} ({:.2}s - {:.2}s): {}",
+            token.speaker_id, token.start_s, token.end_s, token.text
+        );
+        tokens.push(token);
+    }
+
+    transcribe_task.await??;
+    if let Some(pump) = audio_pump {
+        pump.await??;
+    }
 
     println!("Transcription completed. Writing to output file...");
-    let mut output_file = File::create(&output_markdown_filename)?;
 
-    let locked_transcript = transcript_data.lock().await;
-    for result in locked_transcript.iter() {
-        if let Some(nbest) = result.get("NBest").and_then(|n| n.as_array()) {
-            for sentence in nbest {
-                if let Some(words) = sentence.get("Words").and_then(|w| w.as_array()) {
-                    for word in words {
-                        let speaker_id = word.get("SpeakerId").and_then(|s| s.as_str()).unwrap_or("Unknown");
-                        let locked_speakers = speakers.lock().await;
-                        let speaker = locked_speakers.get(speaker_id).unwrap();
-                        let text = word.get("Word").and_then(|w| w.as_str()).unwrap();
-                        let start_time = word.get("Offset").and_then(|o| o.as_f64()).unwrap() / 10_000_000.0;
-                        let end_time = (word.get("Offset").and_then(|o| o.as_f64()).unwrap()
-                            + word.get("Duration").and_then(|d| d.as_f64()).unwrap())
-                            / 10_000_000.0;
-
-                        writeln!(output_file, "- **{}** ({:.2}s - {:.2}s): {}", speaker, start_time, end_time, text)?;
-                    }
-                }
-            }
-        }
+    // The subtitle formats keep the per-word timing; the markdown output
+    // reads better as coalesced speaker turns.
+    let mut words: Vec<WordEntry> = tokens
+        .iter()
+        .map(|token| WordEntry {
+            speaker: token.speaker_id.clone(),
+            text: token.text.clone(),
+            start_s: token.start_s,
+            end_s: token.end_s,
+            ends_sentence: token.ends_sentence,
+        })
+        .collect();
+    let mut utterances = diarize::group_into_utterances(&tokens);
+
+    if let Some(translation_config) = translate::TranslationConfig::resolve() {
+        utterances = translate::translate_utterances(&utterances, &translation_config).await?;
+        // Translation only happens at utterance granularity, so the
+        // per-word view used by the subtitle formats becomes one "word"
+        // per translated utterance.
+        words = utterances
+            .iter()
+            .map(|utterance| WordEntry {
+                speaker: utterance.speaker.clone(),
+                text: utterance.text.clone(),
+                start_s: utterance.start_s,
+                end_s: utterance.end_s,
+                // Each entry is already a whole coalesced utterance, so
+                // it's always safe to cue-break after it.
+                ends_sentence: true,
+            })
+            .collect();
+    }
+
+    let output_format = OutputFormat::resolve();
+    let mut output_file = File::create(&output_markdown_filename)?;
+    match output_format {
+        OutputFormat::Markdown => subtitles::write_markdown(&utterances, &mut output_file)?,
+        OutputFormat::Srt => subtitles::write_srt(&words, &mut output_file)?,
+        OutputFormat::Vtt => subtitles::write_vtt(&words, &mut output_file)?,
     }
 
     println!("Output written to {}", output_markdown_filename);
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,43 @@
+//! Audio source plumbing for live (microphone/socket) transcription.
+//!
+//! Stream mode reads raw PCM chunks off any `AsyncRead` source (stdin, a
+//! TCP socket, ...) and forwards them over an mpsc channel that a
+//! [`crate::backend::TranscriptionBackend`] drains into its own
+//! vendor-specific streaming API.
+
+use azure_cognitiveservices_speech::audio::{AudioStreamFormat, PushAudioInputStream};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::mpsc;
+
+/// Default chunk size used when draining the `AsyncRead` audio source.
+const AUDIO_CHUNK_BYTES: usize = 4096;
+
+/// Reads audio out of `source` in fixed-size chunks and forwards each one
+/// over `chunk_tx`, returning once the source is exhausted.
+///
+/// Runs as its own task so a backend can start draining `chunk_tx`
+/// concurrently with us still filling it.
+pub async fn pump_audio<R>(mut source: R, chunk_tx: mpsc::UnboundedSender<Vec<u8>>) -> crate::backend::Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut buf = vec![0u8; AUDIO_CHUNK_BYTES];
+    loop {
+        let n = source.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        if chunk_tx.send(buf[..n].to_vec()).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Builds a push stream configured for 16 kHz mono 16-bit PCM, the format
+/// our audio sources are expected to supply. Used by backends whose SDK
+/// wants an Azure-shaped push stream rather than a raw byte channel.
+pub fn new_push_stream() -> azure_cognitiveservices_speech::Result<PushAudioInputStream> {
+    let format = AudioStreamFormat::get_default_input_format()?;
+    PushAudioInputStream::create(&format)
+}
@@ -0,0 +1,266 @@
+//! Writers for the markdown transcript and the SRT/WebVTT caption formats.
+//!
+//! The markdown writer consumes diarized `Utterance`s. The SRT and VTT
+//! writers consume the same flat, word-level view of the transcript
+//! (`WordEntry`), group words into cues the same way, and differ in how
+//! they format timestamps and speaker labels.
+
+use crate::diarize::Utterance;
+use std::env;
+use std::io::{self, Write};
+
+/// A single recognized word with its resolved speaker label and timing,
+/// independent of the backend that recognized it.
+#[derive(Debug, Clone)]
+pub struct WordEntry {
+    pub speaker: String,
+    pub text: String,
+    pub start_s: f64,
+    pub end_s: f64,
+    /// Whether this word closes out a sentence, per the backend's
+    /// punctuated display text. See `backend::WordToken::ends_sentence`.
+    pub ends_sentence: bool,
+}
+
+/// Output format selected via `--format <md|srt|vtt>` or `OUTPUT_FORMAT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Markdown,
+    Srt,
+    Vtt,
+}
+
+impl OutputFormat {
+    /// Resolves the format from `--format` on the command line, falling
+    /// back to the `OUTPUT_FORMAT` env var, defaulting to markdown.
+    pub fn resolve() -> Self {
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--format" {
+                if let Some(value) = args.next() {
+                    return Self::parse(&value);
+                }
+            }
+        }
+        match env::var("OUTPUT_FORMAT") {
+            Ok(value) => Self::parse(&value),
+            Err(_) => OutputFormat::Markdown,
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "srt" => OutputFormat::Srt,
+            "vtt" | "webvtt" => OutputFormat::Vtt,
+            _ => OutputFormat::Markdown,
+        }
+    }
+}
+
+/// Maximum cue length before we force a break, matching common captioning
+/// guidance (~7s on screen, ~42 chars per line).
+const MAX_CUE_SECONDS: f64 = 7.0;
+const MAX_CUE_CHARS: usize = 42;
+
+/// A caption cue: one or more consecutive same-speaker words rendered as
+/// a single SRT/VTT entry.
+struct Cue {
+    speaker: String,
+    text: String,
+    start_s: f64,
+    end_s: f64,
+    /// Whether the last word folded into this cue closed a sentence, per
+    /// `WordEntry::ends_sentence`.
+    ends_sentence: bool,
+}
+
+/// Groups words into cues, breaking on a speaker change, a sentence
+/// boundary (the previous word's `ends_sentence`), or when the running
+/// cue would exceed `MAX_CUE_SECONDS`/`MAX_CUE_CHARS`.
+fn build_cues(words: &[WordEntry]) -> Vec<Cue> {
+    let mut cues: Vec<Cue> = Vec::new();
+
+    for word in words {
+        let start_new_cue = match cues.last() {
+            None => true,
+            Some(cue) => {
+                cue.speaker != word.speaker
+                    || cue.ends_sentence
+                    || word.end_s - cue.start_s > MAX_CUE_SECONDS
+                    || cue.text.len() + 1 + word.text.len() > MAX_CUE_CHARS
+            }
+        };
+
+        if start_new_cue {
+            cues.push(Cue {
+                speaker: word.speaker.clone(),
+                text: word.text.clone(),
+                start_s: word.start_s,
+                end_s: word.end_s,
+                ends_sentence: word.ends_sentence,
+            });
+        } else {
+            let cue = cues.last_mut().unwrap();
+            cue.text.push(' ');
+            cue.text.push_str(&word.text);
+            cue.end_s = word.end_s;
+            cue.ends_sentence = word.ends_sentence;
+        }
+    }
+
+    cues
+}
+
+fn format_srt_timestamp(seconds: f64) -> String {
+    let total_ms = (seconds * 1000.0).round() as i64;
+    let ms = total_ms % 1000;
+    let total_s = total_ms / 1000;
+    let s = total_s % 60;
+    let total_m = total_s / 60;
+    let m = total_m % 60;
+    let h = total_m / 60;
+    format!("{:02}:{:02}:{:02},{:03}", h, m, s, ms)
+}
+
+fn format_vtt_timestamp(seconds: f64) -> String {
+    let total_ms = (seconds * 1000.0).round() as i64;
+    let ms = total_ms % 1000;
+    let total_s = total_ms / 1000;
+    let s = total_s % 60;
+    let total_m = total_s / 60;
+    let m = total_m % 60;
+    let h = total_m / 60;
+    format!("{:02}:{:02}:{:02}.{:03}", h, m, s, ms)
+}
+
+/// Writes a `- **Speaker** (start - end): full sentence` markdown list,
+/// one line per diarized utterance rather than one line per word.
+pub fn write_markdown<W: Write>(utterances: &[Utterance], out: &mut W) -> io::Result<()> {
+    for utterance in utterances {
+        writeln!(
+            out,
+            "- **{}** ({:.2}s - {:.2}s): {}",
+            utterance.speaker, utterance.start_s, utterance.end_s, utterance.text
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes numbered SRT cues with `HH:MM:SS,mmm` timestamps.
+pub fn write_srt<W: Write>(words: &[WordEntry], out: &mut W) -> io::Result<()> {
+    for (index, cue) in build_cues(words).iter().enumerate() {
+        writeln!(out, "{}", index + 1)?;
+        writeln!(
+            out,
+            "{} --> {}",
+            format_srt_timestamp(cue.start_s),
+            format_srt_timestamp(cue.end_s)
+        )?;
+        writeln!(out, "{}", cue.text)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Writes a WebVTT file with `HH:MM:SS.mmm` timestamps and `<v Speaker N>`
+/// voice tags so players can surface the speaker label.
+pub fn write_vtt<W: Write>(words: &[WordEntry], out: &mut W) -> io::Result<()> {
+    writeln!(out, "WEBVTT")?;
+    writeln!(out)?;
+    for cue in build_cues(words) {
+        writeln!(
+            out,
+            "{} --> {}",
+            format_vtt_timestamp(cue.start_s),
+            format_vtt_timestamp(cue.end_s)
+        )?;
+        writeln!(out, "<v {}>{}", cue.speaker, cue.text)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(speaker: &str, text: &str, start_s: f64, end_s: f64, ends_sentence: bool) -> WordEntry {
+        WordEntry {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            start_s,
+            end_s,
+            ends_sentence,
+        }
+    }
+
+    #[test]
+    fn breaks_on_speaker_change() {
+        let words = vec![
+            word("Speaker 1", "hello", 0.0, 0.5, false),
+            word("Speaker 2", "hi", 0.5, 1.0, false),
+        ];
+
+        let cues = build_cues(&words);
+
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].text, "hello");
+        assert_eq!(cues[1].text, "hi");
+    }
+
+    #[test]
+    fn breaks_on_sentence_boundary() {
+        let words = vec![
+            word("Speaker 1", "Hello.", 0.0, 0.5, true),
+            word("Speaker 1", "There", 0.5, 1.0, false),
+        ];
+
+        let cues = build_cues(&words);
+
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].text, "Hello.");
+        assert_eq!(cues[1].text, "There");
+    }
+
+    #[test]
+    fn does_not_break_mid_sentence_for_same_speaker() {
+        let words = vec![
+            word("Speaker 1", "Hello", 0.0, 0.5, false),
+            word("Speaker 1", "there", 0.5, 1.0, false),
+        ];
+
+        let cues = build_cues(&words);
+
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].text, "Hello there");
+    }
+
+    #[test]
+    fn breaks_when_cue_duration_exceeds_max_seconds() {
+        let words = vec![
+            word("Speaker 1", "hello", 0.0, 0.5, false),
+            word("Speaker 1", "there", 0.5, MAX_CUE_SECONDS + 1.0, false),
+        ];
+
+        let cues = build_cues(&words);
+
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].text, "hello");
+        assert_eq!(cues[1].text, "there");
+    }
+
+    #[test]
+    fn breaks_when_cue_length_exceeds_max_chars() {
+        let long_word = "a".repeat(MAX_CUE_CHARS);
+        let words = vec![
+            word("Speaker 1", &long_word, 0.0, 0.5, false),
+            word("Speaker 1", "overflow", 0.5, 1.0, false),
+        ];
+
+        let cues = build_cues(&words);
+
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].text, long_word);
+        assert_eq!(cues[1].text, "overflow");
+    }
+}
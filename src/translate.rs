@@ -0,0 +1,84 @@
+//! Optional translation of the diarized transcript into a target
+//! language, via Google Cloud Translation v3.
+
+use crate::diarize::Utterance;
+use std::env;
+
+/// How translated text should be combined with the original when writing
+/// it back out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranslationMode {
+    /// `original / translated`, keeping both languages in each line.
+    Bilingual,
+    /// Replace the line with the translated text only.
+    TranslationOnly,
+}
+
+/// Resolved from `TRANSLATE_TO` (e.g. `es`) and the optional
+/// `TRANSLATE_MODE` (`bilingual`, the default, or `translation_only`).
+pub struct TranslationConfig {
+    pub target_language: String,
+    pub mode: TranslationMode,
+}
+
+impl TranslationConfig {
+    /// Returns `None` when `TRANSLATE_TO` isn't set, i.e. translation was
+    /// not requested.
+    pub fn resolve() -> Option<Self> {
+        let target_language = env::var("TRANSLATE_TO").ok()?;
+        let mode = match env::var("TRANSLATE_MODE").as_deref() {
+            Ok("translation_only") => TranslationMode::TranslationOnly,
+            _ => TranslationMode::Bilingual,
+        };
+        Some(TranslationConfig { target_language, mode })
+    }
+}
+
+/// Utterances per `TranslateTextRequest`, to keep round-trips down
+/// without hitting the API's request size limits.
+const BATCH_SIZE: usize = 100;
+
+/// Translates each utterance's text into `config.target_language`,
+/// batching utterances per request. Speaker labels and timestamps are
+/// carried over unchanged so the subtitle/markdown writers don't need to
+/// know translation happened.
+pub async fn translate_utterances(
+    utterances: &[Utterance],
+    config: &TranslationConfig,
+) -> crate::backend::Result<Vec<Utterance>> {
+    use google_cloud_translate_v3::client::TranslationServiceClient;
+    use google_cloud_translate_v3::model::TranslateTextRequest;
+
+    let project_id = env::var("GOOGLE_PROJECT_ID")?;
+    let client = TranslationServiceClient::new().await?;
+    let parent = format!("projects/{}/locations/global", project_id);
+
+    let mut translated = Vec::with_capacity(utterances.len());
+    for batch in utterances.chunks(BATCH_SIZE) {
+        let contents: Vec<String> = batch.iter().map(|utterance| utterance.text.clone()).collect();
+
+        let request = TranslateTextRequest::new()
+            .set_parent(&parent)
+            .set_contents(contents)
+            .set_target_language_code(&config.target_language);
+
+        let response = client.translate_text(request).send().await?;
+
+        for (utterance, translation) in batch.iter().zip(response.translations) {
+            let text = match config.mode {
+                TranslationMode::TranslationOnly => translation.translated_text,
+                TranslationMode::Bilingual => {
+                    format!("{} / {}", utterance.text, translation.translated_text)
+                }
+            };
+            translated.push(Utterance {
+                speaker: utterance.speaker.clone(),
+                text,
+                start_s: utterance.start_s,
+                end_s: utterance.end_s,
+            });
+        }
+    }
+
+    Ok(translated)
+}
@@ -0,0 +1,45 @@
+//! Minimal RIFF/WAVE parsing: just enough to pull the sample rate and
+//! raw PCM payload back out of a `.wav` file for backends (like
+//! Google's) whose streaming API expects headerless PCM rather than a
+//! WAV file with its RIFF/fmt/data header still attached.
+
+/// Reads `path` and returns `(sample_rate_hertz, pcm_payload)` with the
+/// RIFF/fmt/data header stripped off.
+pub async fn read_pcm(path: &str) -> crate::backend::Result<(i32, Vec<u8>)> {
+    let bytes = tokio::fs::read(path).await?;
+    parse_pcm(&bytes)
+}
+
+fn parse_pcm(bytes: &[u8]) -> crate::backend::Result<(i32, Vec<u8>)> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a RIFF/WAVE file".into());
+    }
+
+    let mut sample_rate = None;
+    let mut data = None;
+    let mut offset = 12;
+
+    while offset + 8 <= bytes.len() {
+        let chunk_id = &bytes[offset..offset + 4];
+        let chunk_size = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let body_start = offset + 8;
+        let body_end = (body_start + chunk_size).min(bytes.len());
+
+        match chunk_id {
+            b"fmt " if chunk_size >= 16 => {
+                sample_rate = Some(u32::from_le_bytes(
+                    bytes[body_start + 4..body_start + 8].try_into().unwrap(),
+                ) as i32);
+            }
+            b"data" => data = Some(bytes[body_start..body_end].to_vec()),
+            _ => {}
+        }
+
+        // Chunks are padded out to an even number of bytes.
+        offset = body_start + chunk_size + (chunk_size % 2);
+    }
+
+    let sample_rate = sample_rate.ok_or("WAV file has no fmt chunk")?;
+    let data = data.ok_or("WAV file has no data chunk")?;
+    Ok((sample_rate, data))
+}